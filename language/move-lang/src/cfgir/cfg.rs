@@ -0,0 +1,67 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A thin block-structured view over a typed function body's basic blocks, built once after
+//! typing and shared by every CFG-based check (liveness today; borrows/unreachable-code later).
+
+use crate::hlir::ast::{BasicBlock, BasicBlocks, Command_, Label};
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct BlockCFG<'a> {
+    blocks: &'a BasicBlocks,
+    successor_map: BTreeMap<Label, BTreeSet<Label>>,
+}
+
+impl<'a> BlockCFG<'a> {
+    pub fn new(blocks: &'a BasicBlocks) -> Self {
+        let successor_map = blocks
+            .iter()
+            .map(|(label, block)| (*label, successors_of(block)))
+            .collect();
+        Self {
+            blocks,
+            successor_map,
+        }
+    }
+
+    pub fn blocks(&self) -> impl Iterator<Item = Label> + '_ {
+        self.blocks.keys().copied()
+    }
+
+    pub fn successors(&self, label: Label) -> impl Iterator<Item = Label> + '_ {
+        self.successor_map[&label].iter().copied()
+    }
+
+    pub fn block(&self, label: Label) -> &'a BasicBlock {
+        &self.blocks[&label]
+    }
+}
+
+fn successors_of(block: &BasicBlock) -> BTreeSet<Label> {
+    let mut out = BTreeSet::new();
+    if let Some(last) = block.back() {
+        match &last.value {
+            Command_::Jump(label) => {
+                out.insert(*label);
+            }
+            Command_::JumpIf {
+                if_true, if_false, ..
+            } => {
+                out.insert(*if_true);
+                out.insert(*if_false);
+            }
+            Command_::Return(_) | Command_::Abort(_) => (),
+            // HLIR translation resolves every `break`/`continue` to a `Jump` at the loop's exit
+            // or continue label as blocks are built, so none should still be standing by the time
+            // a `BlockCFG` is built over them.
+            Command_::Break | Command_::Continue => {
+                panic!("ICE: break/continue should have been lowered to Jump before cfgir")
+            }
+            // A block's last command is always one of the terminators above; anything else means
+            // the block was built without a terminator, which would silently disconnect the
+            // liveness graph rather than signal the bug.
+            _ => panic!("ICE: block does not end in a terminator command"),
+        }
+    }
+    out
+}
@@ -0,0 +1,101 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adapts a function's `BlockCFG` into the generic `LivenessGraph` shape `shared::liveness`
+//! expects, so dead-assignment warnings run as a normal part of the post-typecheck pipeline
+//! instead of living as unused infrastructure.
+
+use crate::{
+    cfgir::cfg::BlockCFG,
+    hlir::ast::{BasicBlock, Command_, Exp, Label, LValue_, UnannotatedExp_, Var},
+    shared::{
+        liveness::{self, Access, LivenessGraph},
+        unique_map::UniqueMap,
+        CompilationEnv, Name,
+    },
+};
+use move_ir_types::location::Loc;
+use std::collections::BTreeMap;
+
+/// Runs the liveness check over one function's CFG. `slots` assigns each local the integer index
+/// it occupies in `names`, matching `shared::liveness`'s `VarSlot` convention.
+pub fn verify(
+    env: &mut CompilationEnv,
+    cfg: &BlockCFG,
+    slots: &UniqueMap<Var, usize>,
+    names: &[Name],
+) {
+    let adapter = Adapter::new(cfg, slots);
+    liveness::analyze(env, &adapter, names.len(), names)
+}
+
+struct Adapter<'a> {
+    cfg: &'a BlockCFG<'a>,
+    accesses: BTreeMap<Label, Vec<Access>>,
+}
+
+impl<'a> Adapter<'a> {
+    fn new(cfg: &'a BlockCFG<'a>, slots: &UniqueMap<Var, usize>) -> Self {
+        let accesses = cfg
+            .blocks()
+            .map(|label| (label, block_accesses(cfg.block(label), slots)))
+            .collect();
+        Self { cfg, accesses }
+    }
+}
+
+impl<'a> LivenessGraph for Adapter<'a> {
+    type Block = Label;
+
+    fn blocks(&self) -> Vec<Label> {
+        self.cfg.blocks().collect()
+    }
+
+    fn successors(&self, block: Label) -> Vec<Label> {
+        self.cfg.successors(block).collect()
+    }
+
+    fn accesses(&self, block: Label) -> &[Access] {
+        &self.accesses[&block]
+    }
+}
+
+fn block_accesses(block: &BasicBlock, slots: &UniqueMap<Var, usize>) -> Vec<Access> {
+    let mut out = Vec::new();
+    for cmd in block {
+        collect_command(&cmd.value, cmd.loc, slots, &mut out);
+    }
+    out
+}
+
+fn collect_command(cmd: &Command_, loc: Loc, slots: &UniqueMap<Var, usize>, out: &mut Vec<Access>) {
+    match cmd {
+        Command_::Assign(lvalues, e) => {
+            collect_exp_reads(e, loc, slots, out);
+            for lv in lvalues {
+                if let LValue_::Var(var, _) = &lv.value {
+                    if let Some(slot) = slots.get(var) {
+                        out.push(Access::Write(*slot, lv.loc));
+                    }
+                }
+            }
+        }
+        Command_::Mutate(lhs, rhs) => {
+            collect_exp_reads(rhs, loc, slots, out);
+            collect_exp_reads(lhs, loc, slots, out);
+        }
+        Command_::Return(e) | Command_::Abort(e) | Command_::IgnoreAndPop { exp: e, .. } => {
+            collect_exp_reads(e, loc, slots, out)
+        }
+        Command_::JumpIf { cond, .. } => collect_exp_reads(cond, loc, slots, out),
+        Command_::Jump(_) | Command_::Break | Command_::Continue => {}
+    }
+}
+
+fn collect_exp_reads(e: &Exp, loc: Loc, slots: &UniqueMap<Var, usize>, out: &mut Vec<Access>) {
+    if let UnannotatedExp_::Use(var) = &e.exp.value {
+        if let Some(slot) = slots.get(var) {
+            out.push(Access::Read(*slot, loc));
+        }
+    }
+}
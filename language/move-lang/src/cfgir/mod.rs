@@ -0,0 +1,39 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Post-typecheck, CFG-based checks over every function body. A function's typed body is lowered
+//! into a `BlockCFG` once here, and every check that needs block/successor structure (today:
+//! dead-assignment liveness) runs against that shared view instead of re-deriving it.
+
+pub mod cfg;
+pub mod liveness;
+
+use crate::{
+    cfgir::cfg::BlockCFG,
+    hlir::ast::{Function, FunctionBody_, SingleType, Var},
+    shared::{unique_map::UniqueMap, CompilationEnv, Name},
+};
+
+/// Runs the CFG-based checks for one function. Called once per function immediately after typing
+/// lowers it into HLIR, so liveness diagnostics land in the same compilation pass as type errors.
+pub fn function(env: &mut CompilationEnv, f: &Function) {
+    let (locals, blocks) = match &f.body.value {
+        FunctionBody_::Defined { locals, blocks, .. } => (locals, blocks),
+        FunctionBody_::Native => return,
+    };
+    let cfg = BlockCFG::new(blocks);
+    let (slots, names) = index_locals(locals);
+    liveness::verify(env, &cfg, &slots, &names);
+}
+
+/// Assigns each local an integer slot matching the order `locals` iterates in, and records its
+/// declared name for diagnostics.
+fn index_locals(locals: &UniqueMap<Var, SingleType>) -> (UniqueMap<Var, usize>, Vec<Name>) {
+    let mut slots = UniqueMap::new();
+    let mut names = Vec::new();
+    for (index, (var, _)) in locals.key_cloned_iter().enumerate() {
+        names.push(var.0.clone());
+        slots.add(var, index).unwrap();
+    }
+    (slots, names)
+}
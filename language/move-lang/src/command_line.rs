@@ -0,0 +1,11 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Names for the command line flags `move-lang` exposes, kept in one place so the binaries that
+//! embed this crate and `Flags` itself agree on the same spelling.
+
+pub const TEST_SHORT: &str = "t";
+pub const TEST: &str = "test";
+
+pub const DENY_WARNINGS_SHORT: &str = "W";
+pub const DENY_WARNINGS: &str = "deny-warnings";
@@ -0,0 +1,169 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured diagnostics. Each `Diagnostic` carries one primary label plus any number of
+//! secondary labels and free-form notes, and renders codespan-style: a caret under the offending
+//! text at each labelled span, with that span's own message inline. This lets a pass explain how
+//! a diagnostic arises across more than one location, e.g. pointing at two declarations with one
+//! message and at the expression that connects them with another, rather than dumping a flat list
+//! of unrelated spans.
+
+use move_ir_types::location::*;
+
+//**************************************************************************************************
+// Types
+//**************************************************************************************************
+
+/// A single labelled span: a location plus the message to show alongside it.
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Label {
+    pub loc: Loc,
+    pub msg: String,
+}
+
+impl Label {
+    fn new(loc: Loc, msg: impl Into<String>) -> Self {
+        Self {
+            loc,
+            msg: msg.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Diagnostic {
+    primary: Label,
+    secondary: Vec<Label>,
+    notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(loc: Loc, msg: impl Into<String>) -> Self {
+        Self {
+            primary: Label::new(loc, msg),
+            secondary: vec![],
+            notes: vec![],
+        }
+    }
+
+    pub fn add_secondary_label(mut self, loc: Loc, msg: impl Into<String>) -> Self {
+        self.secondary.push(Label::new(loc, msg));
+        self
+    }
+
+    pub fn add_secondary_labels(
+        mut self,
+        labels: impl IntoIterator<Item = (Loc, String)>,
+    ) -> Self {
+        self.secondary
+            .extend(labels.into_iter().map(|(loc, msg)| Label::new(loc, msg)));
+        self
+    }
+
+    pub fn add_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn primary_loc(&self) -> Loc {
+        self.primary.loc
+    }
+
+    pub fn secondary_labels(&self) -> &[Label] {
+        &self.secondary
+    }
+
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+}
+
+/// A batch of diagnostics produced by a single compilation pass.
+pub type Errors = Vec<Diagnostic>;
+
+//**************************************************************************************************
+// Legacy shim
+//**************************************************************************************************
+
+/// Lowers the old flat `(Loc, message)` form into a single `Diagnostic`: the first entry becomes
+/// the primary label, the rest become secondary labels. Kept so call sites that only have one
+/// flat list of spans (most of them, today) don't all need to be rewritten at once.
+///
+/// Returns `None` for an empty list. This is a deliberate behavior change from the old
+/// `Errors = Vec<Vec<..>>` form: there, an empty inner vec still got pushed and so still counted
+/// toward `has_errors()`, silently failing the compilation with no diagnostic to show for it. A
+/// `Diagnostic` needs a primary label to exist at all, so there is no way to preserve that old
+/// "errored but unreportable" state here; treating an empty list as no error at all is the
+/// closest honest equivalent.
+pub fn diag_from_tuples(mut labels: Vec<(Loc, String)>) -> Option<Diagnostic> {
+    if labels.is_empty() {
+        return None;
+    }
+    let (primary_loc, primary_msg) = labels.remove(0);
+    Some(Diagnostic::new(primary_loc, primary_msg).add_secondary_labels(labels))
+}
+
+//**************************************************************************************************
+// Rendering
+//**************************************************************************************************
+
+/// What the renderer needs to know about a `Loc` to draw its caret: the source file's display
+/// name, the file's full contents, and the byte range the `Loc` covers within it.
+pub struct ResolvedSpan {
+    pub file: String,
+    pub source: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Renders a single `Diagnostic` codespan-style: the primary span first, with a caret under its
+/// text and the primary message inline, followed by each secondary span in the same style, and
+/// finally any free-form notes. `resolve` maps a `Loc` to its file and byte range; the line and
+/// column are computed from those here.
+pub fn render_diagnostic(diag: &Diagnostic, resolve: impl Fn(Loc) -> ResolvedSpan) -> String {
+    let mut out = String::new();
+    render_label(&mut out, &diag.primary, &resolve);
+    for label in &diag.secondary {
+        render_label(&mut out, label, &resolve);
+    }
+    for note in &diag.notes {
+        out.push_str(&format!("  = note: {}\n", note));
+    }
+    out
+}
+
+fn render_label(out: &mut String, label: &Label, resolve: &impl Fn(Loc) -> ResolvedSpan) {
+    let span = resolve(label.loc);
+    let (line_no, line, col_start, col_end) = line_and_columns(&span);
+    out.push_str(&format!("  --> {}:{}\n", span.file, line_no));
+    out.push_str(&format!("   | {}\n", line));
+    let underline_len = col_end.max(col_start + 1) - col_start;
+    out.push_str(&format!(
+        "   | {}{} {}\n",
+        " ".repeat(col_start),
+        "^".repeat(underline_len),
+        label.msg
+    ));
+}
+
+fn line_and_columns(span: &ResolvedSpan) -> (usize, String, usize, usize) {
+    let mut line_no = 1;
+    let mut line_start = 0;
+    for (i, ch) in span.source.char_indices() {
+        if i >= span.start {
+            break;
+        }
+        if ch == '\n' {
+            line_no += 1;
+            line_start = i + 1;
+        }
+    }
+    let line_end = span.source[line_start..]
+        .find('\n')
+        .map(|i| line_start + i)
+        .unwrap_or_else(|| span.source.len());
+    let line = span.source[line_start..line_end].to_string();
+    let col_start = span.start - line_start;
+    let col_end = (span.end - line_start).min(line.len());
+    (line_no, line, col_start, col_end)
+}
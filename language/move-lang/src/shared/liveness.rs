@@ -0,0 +1,162 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A backward dataflow liveness analysis over a function's control-flow graph, used to warn on
+//! dead assignments and locals that are never read. Callers adapt their own CFG representation
+//! (e.g. `cfgir`) into the small `LivenessGraph` shape this module expects.
+
+use crate::{
+    errors::Diagnostic,
+    shared::{CompilationEnv, Name},
+};
+use move_ir_types::location::*;
+use std::{collections::BTreeMap, hash::Hash};
+
+//**************************************************************************************************
+// Live sets
+//**************************************************************************************************
+
+/// A local's position within a function's locals, used to index into a `LiveSet`.
+pub type VarSlot = usize;
+
+/// For each local slot, the `Loc` of the most recent read that observed it live, or `None` if the
+/// slot is currently dead, i.e. its last assignment (if any) has not been read since.
+#[derive(Clone, Debug)]
+pub struct LiveSet(Vec<Option<Loc>>);
+
+impl LiveSet {
+    pub fn new(num_locals: usize) -> Self {
+        Self(vec![None; num_locals])
+    }
+
+    pub fn is_live(&self, slot: VarSlot) -> bool {
+        self.0[slot].is_some()
+    }
+
+    pub fn mark_read(&mut self, slot: VarSlot, at: Loc) {
+        self.0[slot] = Some(at);
+    }
+
+    pub fn mark_dead(&mut self, slot: VarSlot) {
+        self.0[slot] = None;
+    }
+
+    /// Unions `other` into `self`. A slot already live in `self` keeps its location; a slot dead
+    /// in `self` but live in `other` picks up `other`'s. Returns whether `self` changed.
+    fn join(&mut self, other: &LiveSet) -> bool {
+        let mut changed = false;
+        for (slot, other_slot) in self.0.iter_mut().zip(other.0.iter()) {
+            if slot.is_none() && other_slot.is_some() {
+                *slot = *other_slot;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+//**************************************************************************************************
+// Graph adapter
+//**************************************************************************************************
+
+/// One access to a local, as seen by the liveness pass: either a read (a use) or a write (a
+/// definition/assignment), plus the `Loc` to blame in a diagnostic.
+pub enum Access {
+    Read(VarSlot, Loc),
+    Write(VarSlot, Loc),
+}
+
+/// A function body reduced to what the liveness pass needs: a set of basic blocks, each a
+/// straight-line sequence of `Access`es executed top to bottom, together with the blocks that can
+/// run immediately after each one.
+pub trait LivenessGraph {
+    type Block: Copy + Ord + Hash;
+
+    fn blocks(&self) -> Vec<Self::Block>;
+    fn successors(&self, block: Self::Block) -> Vec<Self::Block>;
+    fn accesses(&self, block: Self::Block) -> &[Access];
+}
+
+//**************************************************************************************************
+// Analysis
+//**************************************************************************************************
+
+/// Runs the liveness analysis over `graph` and reports a warning through `env` for every write
+/// that is dead on arrival, i.e. not live in the set computed for the position right after it.
+/// This covers both locals that are assigned but never subsequently read, and values overwritten
+/// before any read observes them.
+pub fn analyze<G: LivenessGraph>(
+    env: &mut CompilationEnv,
+    graph: &G,
+    num_locals: usize,
+    local_names: &[Name],
+) {
+    let blocks = graph.blocks();
+    let mut live_in: BTreeMap<G::Block, LiveSet> = blocks
+        .iter()
+        .map(|&b| (b, LiveSet::new(num_locals)))
+        .collect();
+
+    // A value defined at the bottom of a loop body can be read back at the top on the next
+    // iteration, so a single backward pass is not enough; iterate until nothing changes.
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &block in &blocks {
+            let live_out = live_out_of(graph, &live_in, block);
+            let mut live = live_out;
+            for access in graph.accesses(block).iter().rev() {
+                apply(&mut live, access);
+            }
+            if live_in.get_mut(&block).unwrap().join(&live) {
+                changed = true;
+            }
+        }
+    }
+
+    // The fixpoint has been reached; walk each block once more, this time reporting every write
+    // that lands on an already-dead slot.
+    for &block in &blocks {
+        let mut live = live_out_of(graph, &live_in, block);
+        for access in graph.accesses(block).iter().rev() {
+            if let Access::Write(slot, loc) = access {
+                if !live.is_live(*slot) {
+                    report_dead_write(env, &local_names[*slot], *loc);
+                }
+            }
+            apply(&mut live, access);
+        }
+    }
+}
+
+fn live_out_of<G: LivenessGraph>(
+    graph: &G,
+    live_in: &BTreeMap<G::Block, LiveSet>,
+    block: G::Block,
+) -> LiveSet {
+    let num_locals = live_in.values().next().map_or(0, |s| s.0.len());
+    let mut live_out = LiveSet::new(num_locals);
+    for succ in graph.successors(block) {
+        live_out.join(&live_in[&succ]);
+    }
+    live_out
+}
+
+fn apply(live: &mut LiveSet, access: &Access) {
+    match *access {
+        Access::Read(slot, loc) => live.mark_read(slot, loc),
+        Access::Write(slot, _) => live.mark_dead(slot),
+    }
+}
+
+fn report_dead_write(env: &mut CompilationEnv, name: &Name, loc: Loc) {
+    let diag = Diagnostic::new(
+        loc,
+        format!(
+            "Unused assignment to '{}'. Consider removing, replacing with '_', or binding to a \
+             variable that is read afterwards",
+            name.value
+        ),
+    );
+    env.add_warning(diag)
+}
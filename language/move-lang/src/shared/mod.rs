@@ -1,7 +1,10 @@
 // Copyright (c) The Diem Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{command_line as cli, errors::Errors};
+use crate::{
+    command_line as cli,
+    errors::{diag_from_tuples, Diagnostic, Errors},
+};
 use fallible::copy_from_slice::copy_slice_to_vec;
 use move_ir_types::location::*;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
@@ -14,6 +17,7 @@ use std::{
 use structopt::*;
 
 pub mod ast_debug;
+pub mod liveness;
 pub mod remembering_unique_map;
 pub mod unique_map;
 pub mod unique_set;
@@ -203,10 +207,22 @@ pub fn shortest_cycle<'a, T: Ord + Hash>(
 // Compilation Env
 //**************************************************************************************************
 
+/// How serious a diagnostic is. Only `Error` fails a compilation outright; `Warning` and
+/// `FutureIncompatible` are reported but otherwise non-fatal, unless `--deny-warnings` is set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Severity {
+    Warning,
+    /// A lint that will become a hard error in a future language version. Reported separately so
+    /// tooling can surface "this will break later" without failing the current build.
+    FutureIncompatible,
+    Error,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CompilationEnv {
     flags: Flags,
     errors: Errors,
+    warnings: Vec<(Severity, Diagnostic)>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -216,18 +232,50 @@ impl CompilationEnv {
         Self {
             flags,
             errors: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 
+    /// Legacy shim: lowers a flat list of `(Loc, message)` pairs into a single `Diagnostic` whose
+    /// first entry becomes the primary label and the rest become secondary labels. A no-op on an
+    /// empty list. Note this is an intentional behavior change from the old `Errors =
+    /// Vec<Vec<..>>` form: there, `add_error(vec![])` still pushed an empty inner vec and so still
+    /// marked the compilation as having failed, just with nothing to show for why. A `Diagnostic`
+    /// can't exist without a primary label, so that "failed silently" state has no equivalent here.
     pub fn add_error(&mut self, e: Vec<(Loc, impl Into<String>)>) {
-        self.errors
-            .push(e.into_iter().map(|(loc, msg)| (loc, msg.into())).collect())
+        let labels = e.into_iter().map(|(loc, msg)| (loc, msg.into())).collect();
+        if let Some(diag) = diag_from_tuples(labels) {
+            self.add_diag(diag)
+        }
+    }
+
+    pub fn add_diag(&mut self, diag: Diagnostic) {
+        self.errors.push(diag)
     }
 
     pub fn add_errors(&mut self, es: Errors) {
         self.errors.extend(es)
     }
 
+    /// Reports a non-fatal diagnostic at the given severity. Under `--deny-warnings` this is
+    /// promoted to a hard error instead of being recorded as a warning.
+    pub fn add_diag_with_severity(&mut self, severity: Severity, diag: Diagnostic) {
+        assert_ne!(severity, Severity::Error, "use add_diag for hard errors");
+        if self.flags.deny_warnings() {
+            self.errors.push(diag);
+        } else {
+            self.warnings.push((severity, diag));
+        }
+    }
+
+    pub fn add_warning(&mut self, diag: Diagnostic) {
+        self.add_diag_with_severity(Severity::Warning, diag)
+    }
+
+    pub fn add_future_incompatible_warning(&mut self, diag: Diagnostic) {
+        self.add_diag_with_severity(Severity::FutureIncompatible, diag)
+    }
+
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
@@ -236,6 +284,10 @@ impl CompilationEnv {
         self.errors.len()
     }
 
+    pub fn count_warnings(&self) -> usize {
+        self.warnings.len()
+    }
+
     pub fn check_errors(&mut self) -> Result<(), Errors> {
         if self.has_errors() {
             Err(std::mem::take(&mut self.errors))
@@ -244,6 +296,16 @@ impl CompilationEnv {
         }
     }
 
+    /// All `FutureIncompatible` diagnostics reported so far, grouped separately from ordinary
+    /// warnings so tooling can surface them as "will become a hard error later" lints.
+    pub fn future_incompatibility_report(&self) -> Vec<&Diagnostic> {
+        self.warnings
+            .iter()
+            .filter(|(severity, _)| *severity == Severity::FutureIncompatible)
+            .map(|(_, diag)| diag)
+            .collect()
+    }
+
     pub fn flags(&self) -> &Flags {
         &self.flags
     }
@@ -292,20 +354,37 @@ pub struct Flags {
         long = cli::TEST,
     )]
     test: bool,
+
+    /// Treat warnings as errors
+    #[structopt(
+        short = cli::DENY_WARNINGS_SHORT,
+        long = cli::DENY_WARNINGS,
+    )]
+    deny_warnings: bool,
 }
 
 impl Flags {
     pub fn empty() -> Self {
-        Self { test: false }
+        Self {
+            test: false,
+            deny_warnings: false,
+        }
     }
 
     pub fn testing() -> Self {
-        Self { test: true }
+        Self {
+            test: true,
+            deny_warnings: false,
+        }
     }
 
     pub fn is_testing(&self) -> bool {
         self.test
     }
+
+    pub fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
 }
 
 //**************************************************************************************************
@@ -0,0 +1,222 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test plans produced from `#[test]`-annotated Move functions: which functions to run, what
+//! argument tuple(s) to run each one with, and what failure (if any) each run is expected to
+//! produce. `move-unit-test` drives its runner off of a `TestPlan` built from these types.
+
+use crate::errors::Diagnostic;
+use move_binary_format::file_format::{CompiledModule, FunctionDefinitionIndex};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId, value::MoveValue};
+use move_ir_types::location::*;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    fmt,
+};
+
+//**************************************************************************************************
+// Plans
+//**************************************************************************************************
+
+pub struct TestPlan {
+    pub module_info: BTreeMap<ModuleId, CompiledModule>,
+    pub module_tests: BTreeMap<ModuleId, ModuleTestPlan>,
+}
+
+impl TestPlan {
+    pub fn new(
+        module_info: BTreeMap<ModuleId, CompiledModule>,
+        module_tests: BTreeMap<ModuleId, ModuleTestPlan>,
+    ) -> Self {
+        Self {
+            module_info,
+            module_tests,
+        }
+    }
+}
+
+pub struct ModuleTestPlan {
+    pub module_id: ModuleId,
+    pub tests: BTreeMap<String, TestCase>,
+}
+
+impl ModuleTestPlan {
+    pub fn new(module_id: ModuleId, tests: BTreeMap<String, TestCase>) -> Self {
+        Self { module_id, tests }
+    }
+}
+
+/// One `#[test]` function together with every argument tuple it should be run with. A plain
+/// `#[test]` produces a single, empty tuple; `#[test(a = 1, b = true)]` produces one tuple parsed
+/// from the attribute; a table-driven test carries one tuple per case, run and reported as
+/// separate `name#0`, `name#1`, ... cases.
+pub struct TestCase {
+    pub test_name_loc: Loc,
+    pub arguments: Vec<Vec<MoveValue>>,
+    pub expected_failure: Option<ExpectedFailure>,
+    /// Overrides the runner's global execution bound for this test, parsed from
+    /// `#[test(timeout = N)]`. `None` means use the runner's default bound.
+    pub timeout: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ExpectedFailure {
+    /// Any abort is acceptable.
+    Expected,
+    /// The abort must carry this code.
+    ExpectedWithCode(u64),
+    /// The abort must carry this code *and* occur at this module/function, so a test asserting a
+    /// specific `assert!`/bounds check can't be satisfied by an unrelated abort that happens to
+    /// share the same code.
+    ExpectedWithCodeAndLocation(u64, ExpectedAbortLocation),
+}
+
+/// Where a test's `#[expected_failure(location = ...)]` attribute says the abort should occur,
+/// resolved at test-plan build time to the module and function the named path refers to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpectedAbortLocation {
+    pub module: ModuleId,
+    pub function: FunctionDefinitionIndex,
+}
+
+impl fmt::Display for ExpectedAbortLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}::<function #{}>", self.module, self.function.0)
+    }
+}
+
+//**************************************************************************************************
+// Typed test arguments
+//**************************************************************************************************
+
+/// A literal token parsed out of a `#[test(...)]` attribute, tagged with the `Loc` of that literal
+/// so a type mismatch can be reported precisely.
+#[derive(Clone, Debug)]
+pub enum TestArgumentLiteral {
+    Bool(bool, Loc),
+    Num(u128, Loc),
+    Address(AccountAddress, Loc),
+    HexString(Vec<u8>, Loc),
+    Vector(Vec<TestArgumentLiteral>, Loc),
+}
+
+impl TestArgumentLiteral {
+    fn loc(&self) -> Loc {
+        match self {
+            TestArgumentLiteral::Bool(_, loc)
+            | TestArgumentLiteral::Num(_, loc)
+            | TestArgumentLiteral::Address(_, loc)
+            | TestArgumentLiteral::HexString(_, loc)
+            | TestArgumentLiteral::Vector(_, loc) => *loc,
+        }
+    }
+
+    /// A short, human-readable name for the literal's own shape, used to report a found/expected
+    /// mismatch without the caller needing to pattern match on it themselves.
+    fn describe(&self) -> String {
+        match self {
+            TestArgumentLiteral::Bool(..) => "a 'bool' literal".to_string(),
+            TestArgumentLiteral::Num(..) => "a number literal".to_string(),
+            TestArgumentLiteral::Address(..) => "an 'address' literal".to_string(),
+            TestArgumentLiteral::HexString(..) => "a hex string literal".to_string(),
+            TestArgumentLiteral::Vector(..) => "a vector literal".to_string(),
+        }
+    }
+}
+
+/// The Move types a `#[test]` argument literal can be converted into, i.e. the primitive and
+/// vector types a test function's parameters are allowed to have.
+#[derive(Clone, Debug)]
+pub enum TestArgumentType {
+    U8,
+    U64,
+    U128,
+    Bool,
+    Address,
+    VectorU8,
+    Vector(Box<TestArgumentType>),
+}
+
+impl fmt::Display for TestArgumentType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestArgumentType::U8 => write!(f, "u8"),
+            TestArgumentType::U64 => write!(f, "u64"),
+            TestArgumentType::U128 => write!(f, "u128"),
+            TestArgumentType::Bool => write!(f, "bool"),
+            TestArgumentType::Address => write!(f, "address"),
+            TestArgumentType::VectorU8 => write!(f, "vector<u8>"),
+            TestArgumentType::Vector(elem) => write!(f, "vector<{}>", elem),
+        }
+    }
+}
+
+/// Converts a literal token into the `MoveValue` its bound parameter expects, the same way a
+/// `Conversion` impl resolves a typed config value from an untyped one.
+pub trait Conversion {
+    fn convert(&self, literal: &TestArgumentLiteral) -> Result<MoveValue, Diagnostic>;
+}
+
+impl Conversion for TestArgumentType {
+    fn convert(&self, literal: &TestArgumentLiteral) -> Result<MoveValue, Diagnostic> {
+        use TestArgumentLiteral as L;
+        match (self, literal) {
+            (TestArgumentType::Bool, L::Bool(b, _)) => Ok(MoveValue::Bool(*b)),
+            (TestArgumentType::U8, L::Num(n, loc)) => u8::try_from(*n)
+                .map(MoveValue::U8)
+                .map_err(|_| out_of_range(*loc, "u8", *n)),
+            (TestArgumentType::U64, L::Num(n, loc)) => u64::try_from(*n)
+                .map(MoveValue::U64)
+                .map_err(|_| out_of_range(*loc, "u64", *n)),
+            (TestArgumentType::U128, L::Num(n, _)) => Ok(MoveValue::U128(*n)),
+            (TestArgumentType::Address, L::Address(addr, _)) => Ok(MoveValue::Address(*addr)),
+            (TestArgumentType::VectorU8, L::HexString(bytes, _)) => {
+                Ok(MoveValue::vector_u8(bytes.clone()))
+            }
+            // A `vector<T>` parameter's literal is itself a vector of `T` literals; convert each
+            // element with the element type and collect into a `MoveValue::Vector`.
+            (TestArgumentType::Vector(elem_ty), L::Vector(elems, _)) => elems
+                .iter()
+                .map(|elem| elem_ty.convert(elem))
+                .collect::<Result<Vec<_>, _>>()
+                .map(MoveValue::Vector),
+            (expected, literal) => Err(Diagnostic::new(
+                literal.loc(),
+                format!(
+                    "Test argument literal does not match its parameter's expected type: \
+                     expected '{}', found {}",
+                    expected,
+                    literal.describe(),
+                ),
+            )),
+        }
+    }
+}
+
+fn out_of_range(loc: Loc, ty: &str, value: u128) -> Diagnostic {
+    Diagnostic::new(
+        loc,
+        format!("Test argument value {} does not fit in a '{}'", value, ty),
+    )
+}
+
+/// Parses the `name = literal` pairs of a `#[test(...)]` attribute into one argument tuple, in the
+/// order given by `params` (the test function's parameter names and types). `attr_loc` is used to
+/// report a diagnostic for a parameter the attribute never assigns a value to.
+pub fn resolve_test_arguments(
+    attr_loc: Loc,
+    params: &[(String, TestArgumentType)],
+    literals: &BTreeMap<String, TestArgumentLiteral>,
+) -> Result<Vec<MoveValue>, Diagnostic> {
+    params
+        .iter()
+        .map(|(name, ty)| match literals.get(name) {
+            Some(literal) => ty.convert(literal),
+            None => Err(Diagnostic::new(
+                attr_loc,
+                format!("No value given for test argument '{}'", name),
+            )),
+        })
+        .collect()
+}
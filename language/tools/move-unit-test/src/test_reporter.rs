@@ -0,0 +1,215 @@
+// Copyright (c) The Diem Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Accumulates the outcome of every test case run by the `TestRunner`, with failures grouped by
+//! module, and renders a one-line summary of the overall pass/fail totals plus a report of the
+//! slowest tests. Individual failures and their reasons are stored but not rendered here.
+
+use crate::format_module_id;
+use move_binary_format::errors::VMError;
+use move_lang::unit_test::{ExpectedAbortLocation, ModuleTestPlan, TestPlan};
+use std::{collections::BTreeMap, fmt};
+
+//**************************************************************************************************
+// Failures
+//**************************************************************************************************
+
+#[derive(Debug)]
+pub enum FailureReason {
+    /// The test was expected to abort, but it didn't.
+    NoAbort,
+    /// The test wasn't expected to abort, but it aborted with this code.
+    Aborted(u64),
+    /// The test aborted, but not with the expected code.
+    WrongAbort(u64, u64),
+    /// The test aborted with the expected code, but not at the expected module/function.
+    WrongAbortLocation(ExpectedAbortLocation, ExpectedAbortLocation),
+    /// The test ran out of ticks before completing.
+    Timeout,
+    /// The VM returned a status this runner doesn't know how to interpret.
+    Unknown,
+}
+
+impl FailureReason {
+    pub fn no_abort() -> Self {
+        Self::NoAbort
+    }
+
+    pub fn aborted(code: u64) -> Self {
+        Self::Aborted(code)
+    }
+
+    pub fn wrong_abort(expected: u64, actual: u64) -> Self {
+        Self::WrongAbort(expected, actual)
+    }
+
+    pub fn wrong_abort_location(
+        expected: ExpectedAbortLocation,
+        actual: ExpectedAbortLocation,
+    ) -> Self {
+        Self::WrongAbortLocation(expected, actual)
+    }
+
+    pub fn timeout() -> Self {
+        Self::Timeout
+    }
+
+    pub fn unknown() -> Self {
+        Self::Unknown
+    }
+}
+
+impl fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoAbort => write!(f, "Test did not error as expected"),
+            Self::Aborted(code) => write!(
+                f,
+                "Test was not expected to error, but it aborted with code {}",
+                code
+            ),
+            Self::WrongAbort(expected, actual) => write!(
+                f,
+                "Test did not abort with expected code {}, abort code {} found instead",
+                expected, actual
+            ),
+            Self::WrongAbortLocation(expected, actual) => write!(
+                f,
+                "Test aborted with the expected code, but at the wrong location: expected {}, \
+                 found {}",
+                expected, actual
+            ),
+            Self::Timeout => write!(f, "Test timed out"),
+            Self::Unknown => write!(f, "Test failed for an unknown reason"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct TestFailure {
+    pub test_name: String,
+    pub vm_error: Option<VMError>,
+    pub failure_reason: FailureReason,
+}
+
+impl TestFailure {
+    pub fn new(
+        failure_reason: FailureReason,
+        test_name: impl Into<String>,
+        vm_error: Option<VMError>,
+    ) -> Self {
+        Self {
+            test_name: test_name.into(),
+            vm_error,
+            failure_reason,
+        }
+    }
+}
+
+//**************************************************************************************************
+// Statistics / results
+//**************************************************************************************************
+
+#[derive(Debug, Default)]
+pub struct TestStatistics {
+    passed: usize,
+    failed: BTreeMap<String, Vec<TestFailure>>,
+    /// Gas ticks actually consumed by every test case that ran, including ones that aborted or
+    /// timed out, keyed by `module::case_name`, so the runner can report per-test usage and call
+    /// out the slowest ones.
+    ticks_used: BTreeMap<String, u64>,
+}
+
+impl TestStatistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn test_success(&mut self) {
+        self.passed += 1;
+    }
+
+    pub fn test_failure(&mut self, failure: TestFailure, test_plan: &ModuleTestPlan) {
+        self.failed
+            .entry(format_module_id(&test_plan.module_id))
+            .or_insert_with(Vec::new)
+            .push(failure);
+    }
+
+    pub fn record_ticks_used(&mut self, test_plan: &ModuleTestPlan, test_name: &str, ticks: u64) {
+        self.ticks_used.insert(
+            format!("{}::{}", format_module_id(&test_plan.module_id), test_name),
+            ticks,
+        );
+    }
+
+    pub fn combine(mut self, other: Self) -> Self {
+        self.passed += other.passed;
+        for (module, failures) in other.failed {
+            self.failed.entry(module).or_insert_with(Vec::new).extend(failures);
+        }
+        self.ticks_used.extend(other.ticks_used);
+        self
+    }
+
+    pub fn passed(&self) -> usize {
+        self.passed
+    }
+
+    pub fn failed(&self) -> usize {
+        self.failed.values().map(Vec::len).sum()
+    }
+
+    /// The `n` tests that consumed the most gas ticks, slowest first.
+    pub fn slowest_tests(&self, n: usize) -> Vec<(&str, u64)> {
+        let mut by_ticks: Vec<_> = self
+            .ticks_used
+            .iter()
+            .map(|(name, ticks)| (name.as_str(), *ticks))
+            .collect();
+        by_ticks.sort_by(|a, b| b.1.cmp(&a.1));
+        by_ticks.truncate(n);
+        by_ticks
+    }
+}
+
+pub struct TestResults {
+    statistics: TestStatistics,
+    tests: TestPlan,
+}
+
+impl TestResults {
+    pub fn new(statistics: TestStatistics, tests: TestPlan) -> Self {
+        Self { statistics, tests }
+    }
+
+    pub fn test_failures_exist(&self) -> bool {
+        self.statistics.failed() > 0
+    }
+
+    pub fn summary(&self) -> String {
+        let total = self.statistics.passed() + self.statistics.failed();
+        format!(
+            "Test result: {}. Total tests: {}; passed: {}; failed: {}",
+            if self.test_failures_exist() { "FAILED" } else { "OK" },
+            total,
+            self.statistics.passed(),
+            self.statistics.failed(),
+        )
+    }
+
+    pub fn tests(&self) -> &TestPlan {
+        &self.tests
+    }
+
+    /// Renders the `n` tests that consumed the most gas ticks, one per line, for tuning execution
+    /// bounds and catching tests drifting toward timeout.
+    pub fn slowest_tests_report(&self, n: usize) -> String {
+        self.statistics
+            .slowest_tests(n)
+            .into_iter()
+            .map(|(name, ticks)| format!("{} ticks\t{}", ticks, name))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
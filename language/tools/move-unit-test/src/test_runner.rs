@@ -7,24 +7,32 @@ use crate::{
 };
 use anyhow::Result;
 use colored::*;
-use move_binary_format::file_format::CompiledModule;
+use move_binary_format::{
+    errors::{Location, VMError},
+    file_format::CompiledModule,
+};
 use move_core_types::{
     gas_schedule::{CostTable, GasAlgebra, GasCost, GasUnits},
     identifier::IdentStr,
     value::serialize_values,
     vm_status::StatusCode,
 };
-use move_lang::unit_test::{ExpectedFailure, ModuleTestPlan, TestPlan};
+use move_lang::unit_test::{ExpectedAbortLocation, ExpectedFailure, ModuleTestPlan, TestPlan};
 use move_vm_runtime::{logging::NoContextLog, move_vm::MoveVM};
 use move_vm_test_utils::InMemoryStorage;
 use move_vm_types::gas_schedule::{zero_cost_schedule, GasStatus};
 use rayon::prelude::*;
 use std::{io::Write, marker::Send, sync::Mutex};
 
+/// A test that passes using at least this fraction of its execution bound is close enough to
+/// timing out that it's worth flagging, even though it didn't actually fail.
+const DEFAULT_GAS_WARN_THRESHOLD: f64 = 0.9;
+
 /// Test state common to all tests
 #[derive(Debug)]
 pub struct SharedTestingConfig {
     execution_bound: u64,
+    gas_warn_threshold: f64,
     cost_table: CostTable,
     starting_storage_state: InMemoryStorage,
 }
@@ -66,12 +74,13 @@ fn setup_test_storage<'a>(
 
 impl TestRunner {
     pub fn new(execution_bound: u64, num_threads: usize, tests: TestPlan) -> Result<Self> {
-        let modules = tests.module_info.values().map(|info| &info.0);
+        let modules = tests.module_info.values();
         let starting_storage_state = setup_test_storage(modules)?;
         Ok(Self {
             testing_config: SharedTestingConfig {
                 starting_storage_state,
                 execution_bound,
+                gas_warn_threshold: DEFAULT_GAS_WARN_THRESHOLD,
                 cost_table: unit_cost_table(),
             },
             num_threads,
@@ -79,6 +88,12 @@ impl TestRunner {
         })
     }
 
+    /// Overrides the fraction of a test's execution bound it can consume before a passing test
+    /// still gets flagged as close to timing out. Defaults to `DEFAULT_GAS_WARN_THRESHOLD`.
+    pub fn set_gas_warn_threshold(&mut self, threshold: f64) {
+        self.testing_config.gas_warn_threshold = threshold;
+    }
+
     pub fn run<W: Write + Send>(self, writer: &Mutex<W>) -> Result<TestResults> {
         rayon::ThreadPoolBuilder::new()
             .num_threads(self.num_threads)
@@ -148,93 +163,199 @@ impl SharedTestingConfig {
             )
             .unwrap();
         };
+        let warn_near_bound = |fn_name: &str, ticks_used: u64, bound: u64| {
+            writeln!(
+                writer.lock().unwrap(),
+                "[ {}    ] {}::{} used {}/{} ticks",
+                "WARN".bold().bright_yellow(),
+                format_module_id(&test_plan.module_id),
+                fn_name,
+                ticks_used,
+                bound,
+            )
+            .unwrap();
+        };
 
         for (function_name, test_info) in &test_plan.tests {
-            let move_vm = MoveVM::new();
-            let mut session = move_vm.new_session(&self.starting_storage_state);
-            let log_context = NoContextLog::new();
-
-            match session.execute_function(
-                &test_plan.module_id,
-                &IdentStr::new(function_name).unwrap(),
-                vec![], // no ty args, at least for now
-                serialize_values(test_info.arguments.iter()),
-                &mut GasStatus::new(&self.cost_table, GasUnits::new(self.execution_bound)),
-                &log_context,
-            ) {
-                Err(err) => match (test_info.expected_failure.as_ref(), err.sub_status()) {
-                    // Ran out of ticks, report a test timeout and log a test failure
-                    _ if err.major_status() == StatusCode::OUT_OF_GAS => {
-                        timeout(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::timeout(), function_name, Some(err)),
-                            &test_plan,
-                        )
-                    }
-                    // Expected the test to not abort, but it aborted with `code`
-                    (None, Some(code)) => {
-                        fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(
-                                FailureReason::aborted(code),
-                                function_name,
-                                Some(err),
-                            ),
-                            &test_plan,
-                        )
-                    }
-                    // Expected the test the abort with a specific `code`, and it did abort with
-                    // that abort code
-                    (Some(ExpectedFailure::ExpectedWithCode(code)), Some(other_code))
-                        if err.major_status() == StatusCode::ABORTED && *code == other_code =>
-                    {
-                        pass(function_name);
-                        stats.test_success();
-                    }
-                    // Expected the test to abort with a specific `code` but it aborted with a
-                    // different `other_code`
-                    (Some(ExpectedFailure::ExpectedWithCode(code)), Some(other_code)) => {
-                        fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(
-                                FailureReason::wrong_abort(*code, other_code),
-                                function_name,
-                                Some(err),
-                            ),
-                            &test_plan,
-                        )
-                    }
-                    // Expected the test to abort and it aborted, but we don't need to check the code
-                    (Some(ExpectedFailure::Expected), Some(_)) => {
-                        pass(function_name);
-                        stats.test_success();
-                    }
-                    // Unexpected return status from the VM, signal that we hit an unknown error.
-                    (_, None) => {
-                        fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::unknown(), function_name, Some(err)),
-                            &test_plan,
-                        )
-                    }
-                },
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::no_abort(), function_name, None),
-                            &test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        pass(function_name);
-                        stats.test_success();
+            // A plain `#[test]` runs its single (possibly empty) argument tuple under the
+            // function's own name; a table-driven test runs each tuple separately and reports it
+            // as `function_name#0`, `function_name#1`, ... so a failing case can be pinpointed.
+            let multiple_cases = test_info.arguments.len() > 1;
+            for (case_index, arguments) in test_info.arguments.iter().enumerate() {
+                let case_name = if multiple_cases {
+                    format!("{}#{}", function_name, case_index)
+                } else {
+                    function_name.clone()
+                };
+
+                let move_vm = MoveVM::new();
+                let mut session = move_vm.new_session(&self.starting_storage_state);
+                let log_context = NoContextLog::new();
+                let bound = test_info.timeout.unwrap_or(self.execution_bound);
+                let mut gas_status = GasStatus::new(&self.cost_table, GasUnits::new(bound));
+
+                let result = session.execute_function(
+                    &test_plan.module_id,
+                    &IdentStr::new(function_name).unwrap(),
+                    vec![], // no ty args, at least for now
+                    serialize_values(arguments.iter()),
+                    &mut gas_status,
+                    &log_context,
+                );
+                let ticks_used = bound - gas_status.remaining_gas().get();
+                stats.record_ticks_used(test_plan, &case_name, ticks_used);
+                let mut passed = false;
+
+                match result {
+                    Err(err) => match (test_info.expected_failure.as_ref(), err.sub_status()) {
+                        // Ran out of ticks, report a test timeout and log a test failure
+                        _ if err.major_status() == StatusCode::OUT_OF_GAS => {
+                            timeout(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(FailureReason::timeout(), &case_name, Some(err)),
+                                &test_plan,
+                            )
+                        }
+                        // Expected the test to not abort, but it aborted with `code`
+                        (None, Some(code)) => {
+                            fail(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(
+                                    FailureReason::aborted(code),
+                                    &case_name,
+                                    Some(err),
+                                ),
+                                &test_plan,
+                            )
+                        }
+                        // Expected the test the abort with a specific `code`, and it did abort with
+                        // that abort code
+                        (Some(ExpectedFailure::ExpectedWithCode(code)), Some(other_code))
+                            if err.major_status() == StatusCode::ABORTED && *code == other_code =>
+                        {
+                            pass(&case_name);
+                            stats.test_success();
+                            passed = true;
+                        }
+                        // Expected the test to abort with a specific `code` at a specific module/
+                        // function; the code matched, so check the abort actually happened where
+                        // expected, not at some unrelated `assert!`/bounds check that shares it.
+                        (
+                            Some(ExpectedFailure::ExpectedWithCodeAndLocation(code, expected_loc)),
+                            Some(other_code),
+                        ) if err.major_status() == StatusCode::ABORTED && *code == other_code => {
+                            match abort_location(&err) {
+                                Some(actual_loc) if actual_loc == *expected_loc => {
+                                    pass(&case_name);
+                                    stats.test_success();
+                                    passed = true;
+                                }
+                                Some(actual_loc) => {
+                                    fail(&case_name);
+                                    stats.test_failure(
+                                        TestFailure::new(
+                                            FailureReason::wrong_abort_location(
+                                                expected_loc.clone(),
+                                                actual_loc,
+                                            ),
+                                            &case_name,
+                                            Some(err),
+                                        ),
+                                        &test_plan,
+                                    )
+                                }
+                                None => {
+                                    fail(&case_name);
+                                    stats.test_failure(
+                                        TestFailure::new(
+                                            FailureReason::unknown(),
+                                            &case_name,
+                                            Some(err),
+                                        ),
+                                        &test_plan,
+                                    )
+                                }
+                            }
+                        }
+                        // Expected the test to abort with a specific `code` at a specific module/
+                        // function, but it aborted with a different `other_code`; the location
+                        // never gets checked since the code itself already doesn't match.
+                        (
+                            Some(ExpectedFailure::ExpectedWithCodeAndLocation(code, _)),
+                            Some(other_code),
+                        ) => {
+                            fail(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(
+                                    FailureReason::wrong_abort(*code, other_code),
+                                    &case_name,
+                                    Some(err),
+                                ),
+                                &test_plan,
+                            )
+                        }
+                        // Expected the test to abort with a specific `code` but it aborted with a
+                        // different `other_code`
+                        (Some(ExpectedFailure::ExpectedWithCode(code)), Some(other_code)) => {
+                            fail(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(
+                                    FailureReason::wrong_abort(*code, other_code),
+                                    &case_name,
+                                    Some(err),
+                                ),
+                                &test_plan,
+                            )
+                        }
+                        // Expected the test to abort and it aborted, but we don't need to check the code
+                        (Some(ExpectedFailure::Expected), Some(_)) => {
+                            pass(&case_name);
+                            stats.test_success();
+                            passed = true;
+                        }
+                        // Unexpected return status from the VM, signal that we hit an unknown error.
+                        (_, None) => {
+                            fail(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(FailureReason::unknown(), &case_name, Some(err)),
+                                &test_plan,
+                            )
+                        }
+                    },
+                    Ok(_) => {
+                        // Expected the test to fail, but it executed
+                        if test_info.expected_failure.is_some() {
+                            fail(&case_name);
+                            stats.test_failure(
+                                TestFailure::new(FailureReason::no_abort(), &case_name, None),
+                                &test_plan,
+                            )
+                        } else {
+                            // Expected the test to execute fully and it did
+                            pass(&case_name);
+                            stats.test_success();
+                            passed = true;
+                        }
                     }
                 }
+
+                if passed && ticks_used as f64 >= bound as f64 * self.gas_warn_threshold {
+                    warn_near_bound(&case_name, ticks_used, bound);
+                }
             }
         }
 
         stats
     }
 }
+
+/// Reads the module/function an abort actually occurred in off of a `VMError`, so it can be
+/// compared against a test's expected abort location.
+fn abort_location(err: &VMError) -> Option<ExpectedAbortLocation> {
+    let module = match err.location() {
+        Location::Module(module_id) => module_id.clone(),
+        _ => return None,
+    };
+    let function = err.offsets().first().map(|(index, _offset)| *index)?;
+    Some(ExpectedAbortLocation { module, function })
+}